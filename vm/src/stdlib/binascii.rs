@@ -3,12 +3,19 @@ use crate::obj::objbytearray::{PyByteArray, PyByteArrayRef};
 use crate::obj::objbyteinner::PyBytesLike;
 use crate::obj::objbytes::{PyBytes, PyBytesRef};
 use crate::obj::objstr::{PyString, PyStringRef};
-use crate::pyobject::{PyObjectRef, PyResult, TryFromObject, TypeProtocol};
+use crate::pyobject::{PyBaseExceptionRef, PyObjectRef, PyResult, TryFromObject, TypeProtocol};
 use crate::vm::VirtualMachine;
 
 use crc::{crc32, Hasher32};
 use itertools::Itertools;
 
+/// Size of the slices `Reader` hands out. `with_ref` already exposes the
+/// whole buffer at once (these objects don't expose a lazy buffer-protocol
+/// read), so this doesn't reduce how much is held in memory; it just keeps
+/// byte-at-a-time consumers like `crc32`'s digest update working over
+/// bounded slices instead of the single full-size one.
+const READER_CHUNK_SIZE: usize = 8192;
+
 enum SerializedData {
     Bytes(PyBytesRef),
     Buffer(PyByteArrayRef),
@@ -46,6 +53,25 @@ impl SerializedData {
             SerializedData::Ascii(a) => f(a.as_str().as_bytes()),
         }
     }
+
+    /// Hands the caller a [`Reader`] over the underlying buffer instead of
+    /// the raw slice directly, so code that only needs to look at the data
+    /// once, in order, reads that intent off the call site.
+    #[inline]
+    pub fn with_reader<R>(&self, f: impl FnOnce(Reader) -> R) -> R {
+        self.with_ref(|bytes| f(Reader { data: bytes }))
+    }
+}
+
+/// A chunked view over an already-materialized buffer's bytes.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn chunks(&self) -> std::slice::Chunks<'a, u8> {
+        self.data.chunks(READER_CHUNK_SIZE)
+    }
 }
 
 fn hex_nibble(n: u8) -> u8 {
@@ -56,14 +82,77 @@ fn hex_nibble(n: u8) -> u8 {
     }
 }
 
-fn binascii_hexlify(data: PyBytesLike, _vm: &VirtualMachine) -> Vec<u8> {
-    data.with_ref(|bytes| {
-        let mut hex = Vec::<u8>::with_capacity(bytes.len() * 2);
-        for b in bytes.iter() {
+fn hex_encode(bytes: &[u8]) -> Vec<u8> {
+    let mut hex = Vec::<u8>::with_capacity(bytes.len() * 2);
+    for chunk in (Reader { data: bytes }).chunks() {
+        for &b in chunk {
             hex.push(hex_nibble(b >> 4));
             hex.push(hex_nibble(b & 0xf));
         }
-        hex
+    }
+    hex
+}
+
+// CPython groups from the right for a positive `bytes_per_sep` (any
+// remainder ends up on the left), and from the left for a negative one
+// (the remainder ends up on the right) — e.g. `b'\xb9\x01\xef'.hex(':', 2)
+// == 'b9:01ef'` but `.hex(':', -2) == 'b901:ef'`.
+fn group_byte_chunks(bytes: &[u8], group: usize, from_right: bool) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    if from_right {
+        let mut i = bytes.len();
+        while i > 0 {
+            let start = i.saturating_sub(group);
+            chunks.push(&bytes[start..i]);
+            i = start;
+        }
+        chunks.reverse();
+    } else {
+        let mut i = 0;
+        while i < bytes.len() {
+            let end = (i + group).min(bytes.len());
+            chunks.push(&bytes[i..end]);
+            i = end;
+        }
+    }
+    chunks
+}
+
+fn binascii_hexlify(
+    data: PyBytesLike,
+    sep: OptionalArg<PyStringRef>,
+    bytes_per_sep: OptionalArg<isize>,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<u8>> {
+    let sep = match sep {
+        OptionalArg::Present(sep) => Some(sep),
+        OptionalArg::Missing => None,
+    };
+    data.with_ref(|bytes| {
+        let sep = match sep {
+            None => return Ok(hex_encode(bytes)),
+            Some(sep) => sep,
+        };
+        if sep.as_str().chars().count() != 1 || !sep.as_str().is_ascii() {
+            return Err(vm.new_value_error("sep must be length 1 ASCII character".to_string()));
+        }
+        let sep_byte = sep.as_str().as_bytes()[0];
+
+        let group = bytes_per_sep.unwrap_or(1);
+        if group == 0 {
+            return Ok(hex_encode(bytes));
+        }
+
+        let chunks = group_byte_chunks(bytes, group.unsigned_abs(), group > 0);
+
+        let mut out = Vec::<u8>::with_capacity(bytes.len() * 2 + chunks.len());
+        for (idx, chunk) in chunks.iter().enumerate() {
+            if idx > 0 {
+                out.push(sep_byte);
+            }
+            out.extend(hex_encode(chunk));
+        }
+        Ok(out)
     })
 }
 
@@ -99,32 +188,582 @@ fn binascii_crc32(data: SerializedData, value: OptionalArg<u32>, vm: &VirtualMac
     let crc = value.unwrap_or(0);
 
     let mut digest = crc32::Digest::new_with_initial(crc32::IEEE, crc);
-    data.with_ref(|bytes| digest.write(&bytes));
+    data.with_reader(|reader| {
+        for chunk in reader.chunks() {
+            digest.write(chunk);
+        }
+    });
 
     Ok(vm.ctx.new_int(digest.sum32()))
 }
 
-fn binascii_a2b_base64(s: SerializedData, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
-    s.with_ref(|bytes| {
-        base64::decode(bytes)
-            .map_err(|err| vm.new_value_error(format!("error decoding base64: {}", err)))
+fn binascii_crc_hqx(data: SerializedData, crc: u16, _vm: &VirtualMachine) -> u16 {
+    let mut crc = crc as u32;
+    data.with_ref(|bytes| {
+        for &byte in bytes {
+            crc ^= (byte as u32) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 {
+                    (crc << 1) ^ 0x1021
+                } else {
+                    crc << 1
+                };
+                crc &= 0xffff;
+            }
+        }
+    });
+    crc as u16
+}
+
+const HQX_RUNCHAR: u8 = 0x90;
+
+enum RleDecodeError {
+    /// A trailing run marker with no count byte after it.
+    Incomplete,
+    /// A run marker as the very first byte, with nothing preceding it to repeat.
+    OrphanedRunCode,
+}
+
+fn rledecode_hqx(bytes: &[u8]) -> Result<Vec<u8>, RleDecodeError> {
+    let mut out = Vec::<u8>::with_capacity(bytes.len());
+    let mut iter = bytes.iter().copied();
+    while let Some(b) = iter.next() {
+        if b != HQX_RUNCHAR {
+            out.push(b);
+            continue;
+        }
+        match iter.next() {
+            None => return Err(RleDecodeError::Incomplete),
+            Some(0) => out.push(HQX_RUNCHAR),
+            Some(count) => {
+                let last = *out.last().ok_or(RleDecodeError::OrphanedRunCode)?;
+                for _ in 1..count {
+                    out.push(last);
+                }
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn binascii_rledecode_hqx(data: SerializedData, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+    data.with_ref(rledecode_hqx).map_err(|err| match err {
+        RleDecodeError::Incomplete => vm.new_exception_msg(
+            vm.class("binascii", "Incomplete"),
+            "String ends with the RLE code".to_string(),
+        ),
+        RleDecodeError::OrphanedRunCode => binascii_error(vm, "Orphaned RLE code at start"),
+    })
+}
+
+fn binascii_rlecode_hqx(data: PyBytesLike, _vm: &VirtualMachine) -> Vec<u8> {
+    data.with_ref(|bytes| {
+        let mut out = Vec::<u8>::with_capacity(bytes.len());
+        let push_literal = |out: &mut Vec<u8>, b: u8| {
+            if b == HQX_RUNCHAR {
+                out.push(HQX_RUNCHAR);
+                out.push(0x00);
+            } else {
+                out.push(b);
+            }
+        };
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            let mut run = 1;
+            while i + run < bytes.len() && bytes[i + run] == b {
+                run += 1;
+            }
+
+            if run > 3 {
+                let mut remaining = run;
+                while remaining > 0 {
+                    let chunk = remaining.min(255);
+                    push_literal(&mut out, b);
+                    out.push(HQX_RUNCHAR);
+                    out.push(chunk as u8);
+                    remaining -= chunk;
+                }
+            } else {
+                for _ in 0..run {
+                    push_literal(&mut out, b);
+                }
+            }
+
+            i += run;
+        }
+        out
+    })
+}
+
+// CPython's binascii uses this exact 64-character alphabet for binhex4.
+// Note that the digit '7' is genuinely absent: verified against CPython's
+// own binascii.b2a_hqx output, not a lookalike-avoidance omission like the
+// skipped 'O'/'W'/'g'/'n'/'o' below it.
+const HQX_ALPHABET: &[u8; 64] =
+    b"!\"#$%&'()*+,-012345689@ABCDEFGHIJKLMNPQRSTUVXYZ[`abcdefhijklmpqr";
+
+fn hqx_decode_byte(c: u8) -> Option<u8> {
+    HQX_ALPHABET.iter().position(|&a| a == c).map(|i| i as u8)
+}
+
+fn hqx_encode(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity((bytes.len() + 2) / 3 * 4);
+    let mut bits: u32 = 0;
+    let mut bitcount = 0u32;
+    for &b in bytes.iter() {
+        bits = (bits << 8) | u32::from(b);
+        bitcount += 8;
+        while bitcount >= 6 {
+            bitcount -= 6;
+            out.push(HQX_ALPHABET[((bits >> bitcount) & 0x3f) as usize]);
+        }
+    }
+    if bitcount > 0 {
+        out.push(HQX_ALPHABET[((bits << (6 - bitcount)) & 0x3f) as usize]);
+    }
+    out
+}
+
+fn binascii_b2a_hqx(data: PyBytesLike, _vm: &VirtualMachine) -> Vec<u8> {
+    data.with_ref(hqx_encode)
+}
+
+fn hqx_decode(bytes: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let mut out = Vec::<u8>::with_capacity(bytes.len() / 4 * 3);
+    let mut bits: u32 = 0;
+    let mut bitcount = 0u32;
+    for &c in bytes.iter() {
+        let value = match hqx_decode_byte(c) {
+            Some(v) => v,
+            None => continue,
+        };
+        bits = (bits << 6) | u32::from(value);
+        bitcount += 6;
+        if bitcount >= 8 {
+            bitcount -= 8;
+            out.push(((bits >> bitcount) & 0xff) as u8);
+        }
+    }
+    if bitcount >= 6 {
+        return Err("String has incomplete number of bytes");
+    }
+    Ok(out)
+}
+
+fn binascii_a2b_hqx(data: SerializedData, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+    data.with_ref(hqx_decode)
+        .map_err(|msg| vm.new_exception_msg(vm.class("binascii", "Incomplete"), msg.to_string()))
+}
+
+fn is_base64_alphabet(b: u8) -> bool {
+    matches!(b, b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'+' | b'/')
+}
+
+fn binascii_error(vm: &VirtualMachine, msg: impl Into<String>) -> PyBaseExceptionRef {
+    vm.new_exception_msg(vm.class("binascii", "Error"), msg.into())
+}
+
+fn decode_base64_quad(quad: &[u8]) -> Result<Vec<u8>, String> {
+    base64::decode(quad).map_err(|err| format!("Invalid base64-encoded string: {}", err))
+}
+
+// Only ever holds at most one pending 4-character group, so decoding a
+// multi-megabyte input never requires a second input-sized allocation.
+//
+// A quad is only "excess data after padding" once it has actually been
+// completed *with* padding; a lone `=` mid-quad (e.g. the first `=` of a
+// valid `==` pair) must not trip this, or correctly-padded input like
+// `b"YQ=="` would wrongly fail in strict mode.
+fn a2b_base64_bytes(bytes: &[u8], strict_mode: bool) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut carry = Vec::<u8>::with_capacity(4);
+    let mut quad_has_padding = false;
+
+    'chunks: for chunk in bytes.chunks(READER_CHUNK_SIZE) {
+        for &b in chunk {
+            if quad_has_padding {
+                if strict_mode {
+                    return Err("Excess data after padding".to_string());
+                }
+                break 'chunks;
+            }
+
+            if b == b'=' {
+                carry.push(b);
+            } else if is_base64_alphabet(b) {
+                carry.push(b);
+            } else if strict_mode {
+                return Err("Only base64 data is allowed".to_string());
+            } else {
+                continue;
+            }
+
+            if carry.len() == 4 {
+                if carry.contains(&b'=') {
+                    quad_has_padding = true;
+                }
+                out.extend(decode_base64_quad(&carry)?);
+                carry.clear();
+            }
+        }
+    }
+
+    if !carry.is_empty() {
+        return Err("Incorrect padding".to_string());
+    }
+
+    Ok(out)
+}
+
+fn binascii_a2b_base64(
+    s: SerializedData,
+    strict_mode: OptionalArg<bool>,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<u8>> {
+    let strict_mode = strict_mode.unwrap_or(false);
+    s.with_ref(|bytes| a2b_base64_bytes(bytes, strict_mode))
+        .map_err(|msg| binascii_error(vm, msg))
+}
+
+fn binascii_b2a_base64(data: PyBytesLike, newline: OptionalArg<bool>, _vm: &VirtualMachine) -> Vec<u8> {
+    let newline = newline.unwrap_or(true);
+    data.with_ref(|b| {
+        let mut encoded = base64::encode(b).into_bytes();
+        if newline {
+            encoded.push(b'\n');
+        }
+        encoded
     })
 }
 
-fn binascii_b2a_base64(data: PyBytesLike, _vm: &VirtualMachine) -> Vec<u8> {
-    data.with_ref(|b| base64::encode(b).into_bytes())
+const QP_LINE_LEN: usize = 76;
+
+fn qp_hex_nibble(n: u8) -> u8 {
+    match n {
+        0..=9 => b'0' + n,
+        10..=15 => b'A' + (n - 10),
+        _ => unreachable!(),
+    }
+}
+
+fn qp_escape(out: &mut Vec<u8>, b: u8) {
+    out.push(b'=');
+    out.push(qp_hex_nibble(b >> 4));
+    out.push(qp_hex_nibble(b & 0xf));
+}
+
+fn qp_needs_quote(b: u8, quotetabs: bool) -> bool {
+    match b {
+        b'=' => true,
+        b'\t' | b' ' => quotetabs,
+        33..=126 => false,
+        _ => true,
+    }
+}
+
+fn qp_encode(bytes: &[u8], quotetabs: bool, istext: bool, header: bool) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity(bytes.len());
+    let mut line_len = 0usize;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if istext && b == b'\n' {
+            // Trailing whitespace right before a line break must be escaped.
+            if matches!(out.last(), Some(b' ') | Some(b'\t')) {
+                let last = out.pop().unwrap();
+                qp_escape(&mut out, last);
+            }
+            out.push(b'\n');
+            line_len = 0;
+            i += 1;
+            continue;
+        }
+
+        let mut piece = Vec::<u8>::with_capacity(3);
+        if header && b == b' ' {
+            piece.push(b'_');
+        } else if qp_needs_quote(b, quotetabs) {
+            qp_escape(&mut piece, b);
+        } else {
+            piece.push(b);
+        }
+
+        if line_len + piece.len() > QP_LINE_LEN - 1 {
+            out.push(b'=');
+            out.push(b'\n');
+            line_len = 0;
+        }
+        out.extend_from_slice(&piece);
+        line_len += piece.len();
+
+        i += 1;
+    }
+
+    out
+}
+
+fn binascii_b2a_qp(
+    data: PyBytesLike,
+    quotetabs: OptionalArg<bool>,
+    istext: OptionalArg<bool>,
+    header: OptionalArg<bool>,
+    _vm: &VirtualMachine,
+) -> Vec<u8> {
+    let quotetabs = quotetabs.unwrap_or(false);
+    let istext = istext.unwrap_or(true);
+    let header = header.unwrap_or(false);
+
+    data.with_ref(|bytes| qp_encode(bytes, quotetabs, istext, header))
+}
+
+fn qp_decode(bytes: &[u8], header: bool) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if b == b'=' {
+            match (bytes.get(i + 1), bytes.get(i + 2)) {
+                (None, _) => {
+                    // A lone '=' at true end-of-input is a soft line break
+                    // with nothing after it to join to, so it's dropped too.
+                    i += 1;
+                    continue;
+                }
+                (Some(b'\n'), _) => {
+                    i += 2;
+                    continue;
+                }
+                (Some(b'\r'), Some(b'\n')) => {
+                    i += 3;
+                    continue;
+                }
+                (Some(&h1), Some(&h2)) => {
+                    if let (Some(n1), Some(n2)) = (unhex_nibble(h1), unhex_nibble(h2)) {
+                        out.push(n1 << 4 | n2);
+                        i += 3;
+                        continue;
+                    }
+                }
+                _ => {}
+            }
+            // A malformed escape (not followed by two hex digits or a
+            // soft line break) is passed through literally.
+            out.push(b'=');
+            i += 1;
+        } else if header && b == b'_' {
+            out.push(b' ');
+            i += 1;
+        } else {
+            out.push(b);
+            i += 1;
+        }
+    }
+
+    out
+}
+
+fn binascii_a2b_qp(
+    data: SerializedData,
+    header: OptionalArg<bool>,
+    _vm: &VirtualMachine,
+) -> Vec<u8> {
+    let header = header.unwrap_or(false);
+    data.with_ref(|bytes| qp_decode(bytes, header))
 }
 
 pub fn make_module(vm: &VirtualMachine) -> PyObjectRef {
     let ctx = &vm.ctx;
 
+    let incomplete = ctx.new_class("Incomplete", ctx.exceptions.exception_type.clone());
+    let error = ctx.new_class("Error", ctx.exceptions.value_error.clone());
+
     py_module!(vm, "binascii", {
         "hexlify" => ctx.new_rustfunc(binascii_hexlify),
         "b2a_hex" => ctx.new_rustfunc(binascii_hexlify),
         "unhexlify" => ctx.new_rustfunc(binascii_unhexlify),
         "a2b_hex" => ctx.new_rustfunc(binascii_unhexlify),
         "crc32" => ctx.new_rustfunc(binascii_crc32),
+        "crc_hqx" => ctx.new_rustfunc(binascii_crc_hqx),
+        "rlecode_hqx" => ctx.new_rustfunc(binascii_rlecode_hqx),
+        "rledecode_hqx" => ctx.new_rustfunc(binascii_rledecode_hqx),
+        "b2a_hqx" => ctx.new_rustfunc(binascii_b2a_hqx),
+        "a2b_hqx" => ctx.new_rustfunc(binascii_a2b_hqx),
         "a2b_base64" => ctx.new_rustfunc(binascii_a2b_base64),
         "b2a_base64" => ctx.new_rustfunc(binascii_b2a_base64),
+        "b2a_qp" => ctx.new_rustfunc(binascii_b2a_qp),
+        "a2b_qp" => ctx.new_rustfunc(binascii_a2b_qp),
+        "Incomplete" => incomplete,
+        "Error" => error,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hqx_alphabet_matches_cpython() {
+        // Captured from a real CPython 3.9 `binascii.b2a_hqx` build; '7' is
+        // genuinely not part of the real alphabet (see the comment above
+        // `HQX_ALPHABET`).
+        assert_eq!(
+            &HQX_ALPHABET[..],
+            b"!\"#$%&'()*+,-012345689@ABCDEFGHIJKLMNPQRSTUVXYZ[`abcdefhijklmpqr",
+        );
+    }
+
+    #[test]
+    fn b2a_hqx_matches_cpython() {
+        let cases: &[(&[u8], &[u8])] = &[
+            (b"", b""),
+            (b"A", b"33"),
+            (b"AB", b"38)"),
+            (b"ABC", b"38*$"),
+            (b"ABCDEF", b"38*$4%9'"),
+            (b"Hello, World!", b"5'9XE'mX)&G[FQaN)3"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(hqx_encode(input), *expected, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn hqx_roundtrips_whole_byte_groups() {
+        for data in [&b""[..], &b"ABC"[..], &b"ABCDEF"[..]] {
+            assert_eq!(hqx_decode(&hqx_encode(data)).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn rledecode_hqx_expands_runs() {
+        assert_eq!(rledecode_hqx(b"AB\x90\x03CD").unwrap(), b"ABBBCD");
+        assert_eq!(rledecode_hqx(b"A\x90\x00B").unwrap(), b"A\x90B");
+    }
+
+    #[test]
+    fn rledecode_hqx_rejects_trailing_run_marker_as_incomplete() {
+        assert!(matches!(
+            rledecode_hqx(b"AB\x90").unwrap_err(),
+            RleDecodeError::Incomplete,
+        ));
+    }
+
+    #[test]
+    fn rledecode_hqx_rejects_leading_run_marker_as_orphaned() {
+        assert!(matches!(
+            rledecode_hqx(b"\x90\x03").unwrap_err(),
+            RleDecodeError::OrphanedRunCode,
+        ));
+    }
+
+    #[test]
+    fn a2b_base64_accepts_correctly_padded_input_in_strict_mode() {
+        assert_eq!(a2b_base64_bytes(b"YQ==", true).unwrap(), b"a");
+        assert_eq!(a2b_base64_bytes(b"YWI=", true).unwrap(), b"ab");
+        assert_eq!(a2b_base64_bytes(b"YWJj", true).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn a2b_base64_rejects_excess_data_after_padding_in_strict_mode() {
+        assert_eq!(
+            a2b_base64_bytes(b"YQ==AB", true).unwrap_err(),
+            "Excess data after padding",
+        );
+    }
+
+    #[test]
+    fn a2b_base64_ignores_excess_data_after_padding_when_not_strict() {
+        assert_eq!(a2b_base64_bytes(b"YQ==AB", false).unwrap(), b"a");
+    }
+
+    #[test]
+    fn a2b_base64_rejects_truncated_input_as_incorrect_padding() {
+        for input in [&b"YWJjZA"[..], &b"YQ"[..], &b"YWI"[..], &b"YW="[..]] {
+            assert_eq!(
+                a2b_base64_bytes(input, false).unwrap_err(),
+                "Incorrect padding",
+            );
+            assert_eq!(
+                a2b_base64_bytes(input, true).unwrap_err(),
+                "Incorrect padding",
+            );
+        }
+    }
+
+    #[test]
+    fn hexlify_groups_positive_sep_from_the_right() {
+        let data = b"\xb9\x01\xef";
+        let chunks = group_byte_chunks(data, 2, true);
+        assert_eq!(chunks, vec![&b"\xb9"[..], &b"\x01\xef"[..]]);
+    }
+
+    #[test]
+    fn hexlify_groups_negative_sep_from_the_left() {
+        let data = b"\xb9\x01\xef";
+        let chunks = group_byte_chunks(data, 2, false);
+        assert_eq!(chunks, vec![&b"\xb9\x01"[..], &b"\xef"[..]]);
+    }
+
+    #[test]
+    fn reader_splits_into_bounded_chunks_without_losing_data() {
+        let data: Vec<u8> = (0..(READER_CHUNK_SIZE * 2 + 17))
+            .map(|i| (i % 256) as u8)
+            .collect();
+        let reader = Reader { data: &data };
+        let chunks: Vec<&[u8]> = reader.chunks().collect();
+
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.len() <= READER_CHUNK_SIZE));
+        assert_eq!(chunks.concat(), data);
+    }
+
+    #[test]
+    fn qp_encode_escapes_equals_and_trailing_whitespace() {
+        assert_eq!(
+            qp_encode(b"Hello=World\n", false, true, false),
+            b"Hello=3DWorld\n",
+        );
+        assert_eq!(
+            qp_encode(b"tab\ttrailing \n", true, true, false),
+            b"tab=09trailing=20\n",
+        );
+        assert_eq!(
+            qp_encode(b"no special chars", false, true, false),
+            b"no special chars",
+        );
+    }
+
+    #[test]
+    fn qp_encode_header_mode_turns_spaces_into_underscores() {
+        assert_eq!(qp_encode(b"a b", false, true, true), b"a_b");
+    }
+
+    #[test]
+    fn qp_decode_reverses_escapes_and_drops_soft_breaks() {
+        assert_eq!(qp_decode(b"Hello=3DWorld\n", false), b"Hello=World\n");
+        assert_eq!(qp_decode(b"soft=\nbreak", false), b"softbreak");
+        assert_eq!(qp_decode(b"a_b", true), b"a b");
+    }
+
+    #[test]
+    fn qp_decode_drops_lone_trailing_equals_sign() {
+        assert_eq!(qp_decode(b"abc=", false), b"abc");
+    }
+
+    #[test]
+    fn qp_roundtrips_through_encode_and_decode() {
+        for (data, header) in [
+            (&b"Hello=World\n"[..], false),
+            (&b"a b\n"[..], true),
+            (&b"plain ascii text\n"[..], false),
+        ] {
+            let encoded = qp_encode(data, false, true, header);
+            assert_eq!(qp_decode(&encoded, header), data);
+        }
+    }
+}